@@ -0,0 +1,80 @@
+pub mod transaction;
+
+pub use transaction::{Transaction, TransactionBehavior};
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// A single SQL statement, held as its SQL text. Bound parameters are not
+/// supported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub sql: String,
+}
+
+impl Statement {
+    /// Creates a new statement with no bound parameters.
+    pub fn new(sql: impl Into<String>) -> Self {
+        Self { sql: sql.into() }
+    }
+}
+
+impl From<&str> for Statement {
+    fn from(sql: &str) -> Self {
+        Statement::new(sql)
+    }
+}
+
+impl From<String> for Statement {
+    fn from(sql: String) -> Self {
+        Statement::new(sql)
+    }
+}
+
+/// The result of executing a single [`Statement`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResultSet {
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Trait implemented by every client capable of talking to a libsql/SQLite
+/// compatible database, whether over HTTP, a local file, or an embedded
+/// replica.
+#[async_trait]
+pub trait DatabaseClient {
+    /// Executes a single statement and returns its result set.
+    async fn execute(&self, stmt: impl Into<Statement> + Send) -> Result<ResultSet>;
+
+    /// Executes a batch of statements in a single round trip.
+    async fn raw_batch(&self, stmts: Vec<Statement>) -> Result<Vec<ResultSet>>;
+
+    /// Opens a new transaction with the default (`DEFERRED`) locking
+    /// behavior.
+    async fn transaction(&self) -> Result<Transaction<'_, Self>>
+    where
+        Self: Sized,
+    {
+        self.transaction_with_behavior(TransactionBehavior::Deferred)
+            .await
+    }
+
+    /// Opens a new transaction using the given [`TransactionBehavior`],
+    /// controlling when SQLite acquires its lock.
+    async fn transaction_with_behavior(
+        &self,
+        behavior: TransactionBehavior,
+    ) -> Result<Transaction<'_, Self>>
+    where
+        Self: Sized,
+    {
+        Transaction::new_with_behavior(self, behavior).await
+    }
+
+    /// Fire-and-forget rollback hook used by [`Transaction`]'s `Drop` impl,
+    /// since `Drop` cannot await. `stmts` is the rollback (and, for a
+    /// savepoint, the matching release) that must run against the same
+    /// connection the transaction was opened on. Implementations should
+    /// enqueue it (e.g. by spawning a task against an owned connection
+    /// handle) rather than blocking the dropping thread.
+    fn start_rollback(&self, stmts: Vec<Statement>);
+}
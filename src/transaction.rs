@@ -2,16 +2,102 @@
 
 use crate::{DatabaseClient, ResultSet, Statement};
 use anyhow::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Source of unique `SAVEPOINT` names. A counter derived purely from
+/// nesting depth collides between sibling scopes opened from the same
+/// parent (two children of the same transaction would both claim `sp1`),
+/// so names are handed out from a single process-wide sequence instead.
+static NEXT_SAVEPOINT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Controls the SQLite locking behavior applied when a transaction begins.
+///
+/// See <https://www.sqlite.org/lang_transaction.html> for the semantics of
+/// each mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransactionBehavior {
+    /// Locks are not acquired until the first statement that reads or
+    /// writes the database. This is SQLite's own default, and preserves
+    /// this crate's previous behavior.
+    #[default]
+    Deferred,
+    /// A write lock is acquired immediately, so that contention is
+    /// reported when the transaction is opened instead of on its first
+    /// write.
+    Immediate,
+    /// An exclusive lock is acquired immediately, preventing other
+    /// connections from reading or writing for the lifetime of the
+    /// transaction.
+    Exclusive,
+}
+
+impl TransactionBehavior {
+    fn begin_stmt(self) -> &'static str {
+        match self {
+            TransactionBehavior::Deferred => "BEGIN DEFERRED",
+            TransactionBehavior::Immediate => "BEGIN IMMEDIATE",
+            TransactionBehavior::Exclusive => "BEGIN EXCLUSIVE",
+        }
+    }
+}
 
 pub struct Transaction<'a, Client: DatabaseClient + ?Sized> {
     client: &'a Client,
+    /// Set once `commit()` or `rollback()` has run, so `Drop` knows not to
+    /// roll back a transaction that was already closed.
+    done: bool,
+    /// `Some(name)` for a transaction opened via [`Transaction::transaction`],
+    /// backed by the `SAVEPOINT` of that name; `None` for a top-level
+    /// transaction using plain `BEGIN`/`COMMIT`/`ROLLBACK`.
+    savepoint: Option<String>,
 }
 
 impl<'a, Client: DatabaseClient + ?Sized> Transaction<'a, Client> {
-    /// Creates a new transaction.
+    /// Creates a new transaction with the default (`DEFERRED`) locking
+    /// behavior.
     pub async fn new(client: &'a Client) -> Result<Transaction<'a, Client>> {
-        client.raw_batch(vec![Statement::new("BEGIN")]).await?;
-        Ok(Self { client })
+        Self::new_with_behavior(client, TransactionBehavior::Deferred).await
+    }
+
+    /// Creates a new transaction using the given [`TransactionBehavior`],
+    /// controlling when SQLite acquires its lock.
+    pub async fn new_with_behavior(
+        client: &'a Client,
+        behavior: TransactionBehavior,
+    ) -> Result<Transaction<'a, Client>> {
+        client
+            .raw_batch(vec![Statement::new(behavior.begin_stmt())])
+            .await?;
+        Ok(Self {
+            client,
+            done: false,
+            savepoint: None,
+        })
+    }
+
+    /// Opens a nested transaction scope backed by a SQLite `SAVEPOINT`,
+    /// bound to the same underlying client connection.
+    ///
+    /// Rolling back an outer transaction discards all of its inner
+    /// savepoints' effects, so functions can freely compose by each opening
+    /// their own `transaction()` without worrying about the caller's
+    /// transaction state. Each call gets a process-wide unique savepoint
+    /// name, so sibling scopes opened from the same parent never collide.
+    ///
+    /// The returned handle borrows `self`, so the outer transaction can't
+    /// be committed or rolled back (both take `self` by value) while a
+    /// child savepoint is still live — misuse that would otherwise let a
+    /// child outlive and operate on a savepoint the parent already
+    /// released is rejected at compile time instead of failing in SQLite.
+    pub async fn transaction(&self) -> Result<Transaction<'_, Client>> {
+        let id = NEXT_SAVEPOINT_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("sp{id}");
+        self.client.execute(format!("SAVEPOINT {name}")).await?;
+        Ok(Transaction {
+            client: self.client,
+            done: false,
+            savepoint: Some(name),
+        })
     }
 
     /// Executes a statement within the current transaction.
@@ -36,15 +122,192 @@ impl<'a, Client: DatabaseClient + ?Sized> Transaction<'a, Client> {
         self.client.execute(stmt.into()).await
     }
 
-    /// Commits the transaction to the database.
-    pub async fn commit(self) -> Result<()> {
-        self.client.execute("COMMIT").await?;
+    /// Executes a batch of statements within the current transaction in a
+    /// single round trip, without emitting its own `BEGIN`/`COMMIT`. This
+    /// is a thin wrapper over the client's `raw_batch`, useful for bulk
+    /// inserts that would otherwise pay one network round trip per
+    /// statement.
+    pub async fn batch(
+        &self,
+        stmts: impl IntoIterator<Item = impl Into<Statement> + Send> + Send,
+    ) -> Result<Vec<ResultSet>> {
+        let stmts: Vec<Statement> = stmts.into_iter().map(Into::into).collect();
+        self.client.raw_batch(stmts).await
+    }
+
+    /// Commits the transaction to the database. For a top-level
+    /// transaction this issues `COMMIT`; for a nested one (opened via
+    /// [`Transaction::transaction`]) it releases the corresponding
+    /// `SAVEPOINT` instead.
+    pub async fn commit(mut self) -> Result<()> {
+        match &self.savepoint {
+            None => {
+                self.client.execute("COMMIT").await?;
+            }
+            Some(name) => {
+                self.client
+                    .execute(format!("RELEASE SAVEPOINT {name}"))
+                    .await?;
+            }
+        }
+        self.done = true;
         Ok(())
     }
 
-    /// Rolls back the transaction, cancelling any of its side-effects.
-    pub async fn rollback(self) -> Result<()> {
-        self.client.execute("ROLLBACK").await?;
+    /// Rolls back the transaction, cancelling any of its side-effects. For
+    /// a top-level transaction this issues `ROLLBACK`; for a nested one it
+    /// rolls back to and releases the corresponding `SAVEPOINT`, discarding
+    /// only that scope's effects.
+    pub async fn rollback(mut self) -> Result<()> {
+        match &self.savepoint {
+            None => {
+                self.client.execute("ROLLBACK").await?;
+            }
+            Some(name) => {
+                self.client
+                    .execute(format!("ROLLBACK TO SAVEPOINT {name}"))
+                    .await?;
+                self.client
+                    .execute(format!("RELEASE SAVEPOINT {name}"))
+                    .await?;
+            }
+        }
+        self.done = true;
         Ok(())
     }
 }
+
+impl<'a, Client: DatabaseClient + ?Sized> Drop for Transaction<'a, Client> {
+    /// Rolls back the transaction if it was dropped without an explicit
+    /// `commit()` or `rollback()` (for example because of an early `?`
+    /// return or a panic between statements), so an open `BEGIN` or
+    /// `SAVEPOINT` never leaks past the `Transaction`'s lifetime. For a
+    /// nested transaction this only unwinds its own savepoint, leaving the
+    /// outer transaction's work so far intact and still open.
+    ///
+    /// `Drop` can't be async, so this schedules the rollback through
+    /// [`DatabaseClient::start_rollback`] instead of awaiting it here.
+    fn drop(&mut self) {
+        if self.done {
+            return;
+        }
+        let stmts = match &self.savepoint {
+            None => vec![Statement::new("ROLLBACK")],
+            Some(name) => vec![
+                Statement::new(format!("ROLLBACK TO SAVEPOINT {name}")),
+                Statement::new(format!("RELEASE SAVEPOINT {name}")),
+            ],
+        };
+        self.client.start_rollback(stmts);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A `DatabaseClient` that records the SQL of every statement it's
+    /// asked to run, in order, instead of talking to a real database.
+    #[derive(Clone, Default)]
+    struct MockClient {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockClient {
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl DatabaseClient for MockClient {
+        async fn execute(&self, stmt: impl Into<Statement> + Send) -> Result<ResultSet> {
+            self.calls.lock().unwrap().push(stmt.into().sql);
+            Ok(ResultSet::default())
+        }
+
+        async fn raw_batch(&self, stmts: Vec<Statement>) -> Result<Vec<ResultSet>> {
+            let mut calls = self.calls.lock().unwrap();
+            calls.extend(stmts.iter().map(|stmt| stmt.sql.clone()));
+            Ok(vec![ResultSet::default(); stmts.len()])
+        }
+
+        fn start_rollback(&self, stmts: Vec<Statement>) {
+            let mut calls = self.calls.lock().unwrap();
+            calls.extend(stmts.iter().map(|stmt| stmt.sql.clone()));
+        }
+    }
+
+    #[test]
+    fn begin_stmt_matches_behavior() {
+        assert_eq!(TransactionBehavior::Deferred.begin_stmt(), "BEGIN DEFERRED");
+        assert_eq!(TransactionBehavior::Immediate.begin_stmt(), "BEGIN IMMEDIATE");
+        assert_eq!(TransactionBehavior::Exclusive.begin_stmt(), "BEGIN EXCLUSIVE");
+    }
+
+    #[tokio::test]
+    async fn commit_marks_done_so_drop_is_a_no_op() {
+        let client = MockClient::default();
+        let tx = Transaction::new(&client).await.unwrap();
+        tx.commit().await.unwrap();
+        assert_eq!(client.calls(), vec!["BEGIN DEFERRED", "COMMIT"]);
+    }
+
+    #[tokio::test]
+    async fn rollback_marks_done_so_drop_is_a_no_op() {
+        let client = MockClient::default();
+        let tx = Transaction::new(&client).await.unwrap();
+        tx.rollback().await.unwrap();
+        assert_eq!(client.calls(), vec!["BEGIN DEFERRED", "ROLLBACK"]);
+    }
+
+    #[tokio::test]
+    async fn drop_without_commit_or_rollback_rolls_back() {
+        let client = MockClient::default();
+        {
+            let _tx = Transaction::new(&client).await.unwrap();
+        }
+        assert_eq!(client.calls(), vec!["BEGIN DEFERRED", "ROLLBACK"]);
+    }
+
+    #[tokio::test]
+    async fn drop_of_nested_transaction_rolls_back_only_its_savepoint() {
+        let client = MockClient::default();
+        let outer = Transaction::new(&client).await.unwrap();
+        {
+            let _inner = outer.transaction().await.unwrap();
+        }
+        outer.commit().await.unwrap();
+
+        let calls = client.calls();
+        assert_eq!(calls[0], "BEGIN DEFERRED");
+        let savepoint = calls[1]
+            .strip_prefix("SAVEPOINT ")
+            .expect("second call should open a savepoint")
+            .to_string();
+        assert_eq!(calls[2], format!("ROLLBACK TO SAVEPOINT {savepoint}"));
+        assert_eq!(calls[3], format!("RELEASE SAVEPOINT {savepoint}"));
+        assert_eq!(calls[4], "COMMIT");
+    }
+
+    #[tokio::test]
+    async fn batch_sends_all_statements_through_raw_batch() {
+        let client = MockClient::default();
+        let tx = Transaction::new(&client).await.unwrap();
+        tx.batch(vec!["INSERT INTO t VALUES (1)", "INSERT INTO t VALUES (2)"])
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+
+        assert_eq!(
+            client.calls(),
+            vec![
+                "BEGIN DEFERRED",
+                "INSERT INTO t VALUES (1)",
+                "INSERT INTO t VALUES (2)",
+                "COMMIT",
+            ]
+        );
+    }
+}